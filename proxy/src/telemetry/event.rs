@@ -0,0 +1,103 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ctx;
+
+/// Events reported by the proxy's telemetry sensors.
+#[derive(Clone, Debug)]
+pub enum Event {
+    TransportOpen(Arc<ctx::transport::Ctx>),
+    TransportClose(Arc<ctx::transport::Ctx>, TransportClose),
+    TransportReuse(Arc<ctx::transport::Ctx>, TransportReuse),
+    TlsHandshake(Arc<ctx::transport::Client>, TlsHandshake),
+}
+
+/// Reports that a transport has closed, however it ended.
+#[derive(Clone, Debug)]
+pub struct TransportClose {
+    pub duration: Duration,
+    pub clean: bool,
+    pub reason: CloseReason,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    /// Stats on the discrete protocol messages read from the transport, if a
+    /// framing decoder was installed for it.
+    pub rx_frames: Option<FrameStats>,
+    /// Stats on the discrete protocol messages written to the transport, if a
+    /// framing decoder was installed for it.
+    pub tx_frames: Option<FrameStats>,
+}
+
+/// Aggregate statistics about the discrete protocol messages observed on one
+/// direction of a transport, once a framing decoder has been installed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameStats {
+    pub frames: u64,
+    pub min_size: Option<usize>,
+    pub max_size: Option<usize>,
+    pub total_size: u64,
+}
+
+impl FrameStats {
+    pub fn mean_size(&self) -> Option<f64> {
+        if self.frames == 0 {
+            None
+        } else {
+            Some(self.total_size as f64 / self.frames as f64)
+        }
+    }
+
+    /// Combines these stats with another period's, as when a pooled
+    /// connection's frame counts from an earlier checkout are carried
+    /// forward alongside its byte counts and opened-at time.
+    pub fn merge(self, other: FrameStats) -> FrameStats {
+        FrameStats {
+            frames: self.frames + other.frames,
+            total_size: self.total_size + other.total_size,
+            min_size: match (self.min_size, other.min_size) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, None) => a,
+                (None, b) => b,
+            },
+            max_size: match (self.max_size, other.max_size) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, None) => a,
+                (None, b) => b,
+            },
+        }
+    }
+}
+
+/// Reports that an idle, pooled transport has been checked back out and
+/// resumed use.
+#[derive(Clone, Debug)]
+pub struct TransportReuse {
+    /// How long the connection sat idle in the pool before this reuse.
+    pub idle: Duration,
+}
+
+/// Why a transport stopped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CloseReason {
+    /// The transport was shut down normally, e.g. the application dropped it.
+    Normal,
+    /// A read or write returned a non-recoverable `io::Error`.
+    Error,
+    /// The transport went longer than its configured idle timeout without a
+    /// successful read or write.
+    IdleTimeout,
+    /// The transport was handed back to the pool rather than torn down; it
+    /// may be reused by a later checkout. The pool drops it instead if it
+    /// already holds its maximum of idle connections for that key.
+    Parked,
+}
+
+/// Reports the outcome of a client connection's TLS handshake, if it
+/// attempted one.
+#[derive(Clone, Debug)]
+pub struct TlsHandshake {
+    /// How long the handshake took, or `None` if the connection never
+    /// attempted one (e.g. it stayed plaintext).
+    pub latency: Option<Duration>,
+    pub status: ctx::transport::TlsStatus,
+}
@@ -1,104 +1,350 @@
 use bytes::Buf;
 use futures::{Async, Future, Poll};
+use std::fmt;
 use std::io;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio_connect;
+use tokio_timer::Delay;
 use tokio::io::{AsyncRead, AsyncWrite};
 
+use tokio_io::codec::Decoder;
+
 use connection::{self, Peek};
 use ctx;
 use telemetry::event;
 
+use self::framing::FrameSense;
+use self::otel::OtelExporter;
+
+pub mod framing;
+pub mod otel;
+pub mod pool;
+
+/// Builds a fresh frame-counting `FrameSense` for one direction of a
+/// transport, so that `Connect` can hand a matching decoder to every
+/// transport it produces without needing that decoder's concrete type to
+/// appear in `Connect`'s own type parameters.
+#[derive(Clone)]
+struct FramingFactory(Arc<dyn Fn() -> Box<dyn FrameSense> + Send + Sync>);
+
+impl FramingFactory {
+    fn new<D>(decoder: D) -> Self
+    where
+        D: Decoder + Clone + Send + 'static,
+    {
+        FramingFactory(Arc::new(move || -> Box<dyn FrameSense> {
+            Box::new(framing::FrameCounter::new(decoder.clone()))
+        }))
+    }
+
+    fn make(&self) -> Box<dyn FrameSense> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for FramingFactory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FramingFactory").finish()
+    }
+}
+
 /// Wraps a transport with telemetry.
 #[derive(Debug)]
 pub struct Transport<T> {
-    io: T,
+    io: Option<T>,
     inner: Option<Inner>,
+    pool: Option<(pool::Pool<T>, pool::Key)>,
     ctx: Arc<ctx::transport::Ctx>
 }
 
 #[derive(Debug)]
 struct Inner {
     handle: super::Handle,
+    otel: Option<OtelExporter>,
     opened_at: Instant,
+    last_activity: Instant,
+    idle_timer: Option<IdleTimer>,
+    rx_framing: Option<Box<dyn FrameSense>>,
+    tx_framing: Option<Box<dyn FrameSense>>,
+    /// Frame stats from checkouts prior to this one, if this connection was
+    /// pooled and a framing decoder was ever installed on it before.
+    rx_frames_prior: Option<event::FrameStats>,
+    tx_frames_prior: Option<event::FrameStats>,
 
     rx_bytes: u64,
     tx_bytes: u64,
 }
 
+/// Combines frame stats carried forward from earlier checkouts with the
+/// current checkout's live decoder, if any, so a pooled connection's frame
+/// counts span its whole pooled lifetime just as its byte counts do.
+fn merge_frames(
+    prior: Option<event::FrameStats>,
+    current: Option<Box<dyn FrameSense>>,
+) -> Option<event::FrameStats> {
+    let current = current.map(|f| f.stats());
+    match (prior, current) {
+        (Some(a), Some(b)) => Some(a.merge(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+/// Shuts a `Transport` down if it goes too long without a successful
+/// `read`/`write`/`write_buf`.
+struct IdleTimer {
+    timeout: Duration,
+    delay: Delay,
+}
+
+impl IdleTimer {
+    fn new(timeout: Duration) -> Self {
+        IdleTimer {
+            timeout,
+            delay: Delay::new(Instant::now() + timeout),
+        }
+    }
+
+    /// Pushes the deadline out to `timeout` from `from`.
+    fn reset(&mut self, from: Instant) {
+        self.delay.reset(from + self.timeout);
+    }
+
+    /// Returns `true` once `timeout` has elapsed since the last reset.
+    fn poll_expired(&mut self) -> bool {
+        match self.delay.poll() {
+            Ok(Async::Ready(())) => true,
+            Ok(Async::NotReady) => false,
+            // A timer error (e.g. the runtime shutting down) isn't an idle
+            // connection; let the normal IO error paths handle that.
+            Err(_) => false,
+        }
+    }
+}
+
+impl fmt::Debug for IdleTimer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IdleTimer")
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
 /// Builds client transports with telemetry.
 #[derive(Clone, Debug)]
 pub struct Connect<C> {
     underlying: C,
     handle: super::Handle,
+    otel: Option<OtelExporter>,
+    pool: Option<pool::Pool<connection::Connection>>,
+    idle_timeout: Option<Duration>,
+    framing: Option<FramingFactory>,
     ctx: Arc<ctx::transport::Client>,
 }
 
-/// Adds telemetry to a pending client transport.
-#[derive(Clone, Debug)]
-pub struct Connecting<C: tokio_connect::Connect> {
-    underlying: C::Future,
-    handle: super::Handle,
-    ctx: Arc<ctx::transport::Client>,
+/// Adds telemetry to a pending client transport, either by driving the
+/// underlying connect future or, if a pooled connection was checked out,
+/// by completing immediately.
+#[derive(Debug)]
+pub enum Connecting<C: tokio_connect::Connect> {
+    Reused(Option<Transport<C::Connected>>),
+    New {
+        underlying: C::Future,
+        handle: super::Handle,
+        otel: Option<OtelExporter>,
+        pool: Option<(pool::Pool<C::Connected>, pool::Key)>,
+        idle_timeout: Option<Duration>,
+        framing: Option<FramingFactory>,
+        ctx: Arc<ctx::transport::Client>,
+    },
 }
 
 // === impl Transport ===
 
+impl<T> Transport<T> {
+    fn io_mut(&mut self) -> &mut T {
+        self.io.as_mut().expect("transport io already taken")
+    }
+
+    fn io_ref(&self) -> &T {
+        self.io.as_ref().expect("transport io already taken")
+    }
+
+    /// Marks the transport as active, resetting the idle timeout if one is set.
+    fn record_activity(&mut self) {
+        if let Some(inner) = self.inner.as_mut() {
+            let now = Instant::now();
+            inner.last_activity = now;
+            if let Some(timer) = inner.idle_timer.as_mut() {
+                timer.reset(now);
+            }
+        }
+    }
+}
+
 impl<T: AsyncRead + AsyncWrite> Transport<T> {
     /// Wraps a transport with telemetry and emits a transport open event.
     pub(super) fn open(
         io: T,
         opened_at: Instant,
         handle: &super::Handle,
+        otel: Option<&OtelExporter>,
+        idle_timeout: Option<Duration>,
         ctx: Arc<ctx::transport::Ctx>,
     ) -> Self {
         let mut handle = handle.clone();
 
         handle.send(|| event::Event::TransportOpen(Arc::clone(&ctx)));
+        if let Some(otel) = otel {
+            otel.open(&ctx);
+        }
 
         Transport {
-            io,
+            io: Some(io),
             ctx,
+            pool: None,
             inner: Some(Inner {
                 handle,
+                otel: otel.cloned(),
                 opened_at,
+                last_activity: opened_at,
+                idle_timer: idle_timeout.map(IdleTimer::new),
+                rx_framing: None,
+                tx_framing: None,
+                rx_frames_prior: None,
+                tx_frames_prior: None,
                 rx_bytes: 0,
                 tx_bytes: 0,
             }),
         }
     }
 
+    /// Reconstructs a telemetry-wrapped transport from a connection that was
+    /// checked out of a `pool::Pool`, emitting a `TransportReuse` event
+    /// reporting how long it sat idle and starting a fresh span for this
+    /// checkout, so that its eventual close reports the connection's full
+    /// lifetime.
+    fn reopen(
+        idle: pool::Idle<T>,
+        idle_timeout: Option<Duration>,
+        pool: pool::Pool<T>,
+        key: pool::Key,
+    ) -> Self {
+        let idle_duration = idle.parked_at.elapsed();
+        debug!("reusing pooled connection after {:?} idle", idle_duration);
+
+        let mut handle = idle.handle;
+        if let Some(otel) = &idle.otel {
+            otel.open(&idle.ctx);
+        }
+        let ctx = idle.ctx;
+        let reused_ctx = ctx.clone();
+        handle.send(move || {
+            event::Event::TransportReuse(
+                reused_ctx,
+                event::TransportReuse {
+                    idle: idle_duration,
+                },
+            )
+        });
+
+        let now = Instant::now();
+        Transport {
+            io: Some(idle.io),
+            ctx,
+            pool: Some((pool, key)),
+            inner: Some(Inner {
+                handle,
+                otel: idle.otel,
+                opened_at: idle.opened_at,
+                last_activity: now,
+                idle_timer: idle_timeout.map(IdleTimer::new),
+                rx_framing: None,
+                tx_framing: None,
+                rx_frames_prior: idle.rx_frames,
+                tx_frames_prior: idle.tx_frames,
+                rx_bytes: idle.rx_bytes,
+                tx_bytes: idle.tx_bytes,
+            }),
+        }
+    }
+
+    /// Marks this transport as belonging to `pool`, so that a clean `Drop`
+    /// parks it for reuse instead of tearing it down.
+    fn with_pool(mut self, pool: pool::Pool<T>, key: pool::Key) -> Self {
+        self.pool = Some((pool, key));
+        self
+    }
+
+    /// Installs a framing decoder, so that subsequent reads and writes are
+    /// also counted as discrete protocol messages. The decoder only ever
+    /// observes a copy of the bytes; it never alters what the application
+    /// reads or writes.
+    ///
+    /// Called by `Connect`/`Connecting` once a transport is ready to hand
+    /// out, for every `Connect` configured via `with_framing` — the protocol
+    /// a given `Connect` speaks is known by its caller (e.g. from its own
+    /// peek-based protocol detection) before the decoder is ever configured.
+    fn install_framing(&mut self, factory: &FramingFactory) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.rx_framing = Some(factory.make());
+            inner.tx_framing = Some(factory.make());
+        }
+    }
+
     /// Wraps an operation on the underlying transport with error telemetry.
     ///
     /// If the transport operation results in a non-recoverable error, a transport close
-    /// event is emitted.
+    /// event is emitted. If the transport has gone idle for longer than its configured
+    /// timeout, the operation is preempted by an idle-timeout close instead of being
+    /// attempted at all.
     fn sense_err<F, U>(&mut self, op: F) -> io::Result<U>
     where
         F: FnOnce(&mut T) -> io::Result<U>,
     {
-        match op(&mut self.io) {
+        let timed_out = self
+            .inner
+            .as_mut()
+            .and_then(|inner| inner.idle_timer.as_mut())
+            .map(IdleTimer::poll_expired)
+            .unwrap_or(false);
+        if timed_out {
+            return Err(self.close_idle_timeout());
+        }
+
+        match op(self.io_mut()) {
             Ok(v) => Ok(v),
             Err(e) => {
                 if e.kind() != io::ErrorKind::WouldBlock {
                     if let Some(Inner {
                         mut handle,
+                        otel,
                         opened_at,
+                        last_activity: _,
+                        idle_timer: _,
+                        rx_framing,
+                        tx_framing,
+                        rx_frames_prior,
+                        tx_frames_prior,
                         rx_bytes,
                         tx_bytes,
                     }) = self.inner.take()
                     {
                         let ctx = self.ctx.clone();
-                        handle.send(move || {
-                            let duration = opened_at.elapsed();
-                            let ev = event::TransportClose {
-                                duration,
-                                clean: false,
-                                rx_bytes,
-                                tx_bytes,
-                            };
-                            event::Event::TransportClose(ctx, ev)
-                        });
+                        let ev = event::TransportClose {
+                            duration: opened_at.elapsed(),
+                            clean: false,
+                            reason: event::CloseReason::Error,
+                            rx_bytes,
+                            tx_bytes,
+                            rx_frames: merge_frames(rx_frames_prior, rx_framing),
+                            tx_frames: merge_frames(tx_frames_prior, tx_framing),
+                        };
+                        if let Some(otel) = otel {
+                            otel.close(&ctx, &ev);
+                        }
+                        handle.send(move || event::Event::TransportClose(ctx, ev));
                     }
                 }
 
@@ -106,38 +352,139 @@ impl<T: AsyncRead + AsyncWrite> Transport<T> {
             }
         }
     }
-}
 
-impl<T> Drop for Transport<T> {
-    fn drop(&mut self) {
+    /// Tears the transport down after it's gone too long without activity,
+    /// emitting a `TransportClose` with `reason: IdleTimeout`.
+    fn close_idle_timeout(&mut self) -> io::Error {
+        let _ = self.io_mut().shutdown();
+
+        // An idle connection is never worth returning to the pool.
+        self.pool = None;
+
         if let Some(Inner {
             mut handle,
+            otel,
             opened_at,
+            last_activity,
+            idle_timer: _,
+            rx_framing,
+            tx_framing,
+            rx_frames_prior,
+            tx_frames_prior,
             rx_bytes,
             tx_bytes,
         }) = self.inner.take()
         {
+            warn!(
+                "idle timeout: no activity for {:?}",
+                last_activity.elapsed()
+            );
+
+            let ctx = self.ctx.clone();
+            let ev = event::TransportClose {
+                duration: opened_at.elapsed(),
+                clean: false,
+                reason: event::CloseReason::IdleTimeout,
+                rx_bytes,
+                tx_bytes,
+                rx_frames: merge_frames(rx_frames_prior, rx_framing),
+                tx_frames: merge_frames(tx_frames_prior, tx_framing),
+            };
+            if let Some(otel) = otel {
+                otel.close(&ctx, &ev);
+            }
+            handle.send(move || event::Event::TransportClose(ctx, ev));
+        }
+
+        io::Error::new(io::ErrorKind::TimedOut, "transport idle timeout")
+    }
+}
+
+impl<T> Drop for Transport<T> {
+    fn drop(&mut self) {
+        let Inner {
+            mut handle,
+            otel,
+            opened_at,
+            last_activity: _,
+            idle_timer: _,
+            rx_framing,
+            tx_framing,
+            rx_frames_prior,
+            tx_frames_prior,
+            rx_bytes,
+            tx_bytes,
+        } = match self.inner.take() {
+            Some(inner) => inner,
+            None => return,
+        };
+
+        if let (Some((pool, key)), Some(io)) = (self.pool.take(), self.io.take()) {
+            let rx_frames = merge_frames(rx_frames_prior, rx_framing);
+            let tx_frames = merge_frames(tx_frames_prior, tx_framing);
+
             let ctx = self.ctx.clone();
-            handle.send(move || {
-                let duration = opened_at.elapsed();
-                let ev = event::TransportClose {
-                    clean: true,
-                    duration,
+            let ev = event::TransportClose {
+                clean: true,
+                duration: opened_at.elapsed(),
+                reason: event::CloseReason::Parked,
+                rx_bytes,
+                tx_bytes,
+                rx_frames,
+                tx_frames,
+            };
+            if let Some(otel) = &otel {
+                otel.close(&ctx, &ev);
+            }
+            handle.send(move || event::Event::TransportClose(ctx, ev));
+
+            pool.put(
+                key,
+                pool::Idle {
+                    io,
+                    ctx: self.ctx.clone(),
+                    handle,
+                    otel,
+                    opened_at,
                     rx_bytes,
                     tx_bytes,
-                };
-                event::Event::TransportClose(ctx, ev)
-            });
+                    rx_frames,
+                    tx_frames,
+                    parked_at: Instant::now(),
+                },
+            );
+            return;
+        }
+
+        let ctx = self.ctx.clone();
+        let ev = event::TransportClose {
+            clean: true,
+            duration: opened_at.elapsed(),
+            reason: event::CloseReason::Normal,
+            rx_bytes,
+            tx_bytes,
+            rx_frames: merge_frames(rx_frames_prior, rx_framing),
+            tx_frames: merge_frames(tx_frames_prior, tx_framing),
+        };
+        if let Some(otel) = otel {
+            otel.close(&ctx, &ev);
         }
+        handle.send(move || event::Event::TransportClose(ctx, ev));
     }
 }
 
 impl<T: AsyncRead + AsyncWrite> io::Read for Transport<T> {
-    fn read(&mut self, mut buf: &mut [u8]) -> io::Result<usize> {
-        let bytes = self.sense_err(move |io| io.read(buf))?;
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.sense_err(|io| io.read(buf))?;
 
+        if bytes > 0 {
+            self.record_activity();
+        }
         if let Some(inner) = self.inner.as_mut() {
             inner.rx_bytes += bytes as u64;
+            if let Some(framing) = inner.rx_framing.as_mut() {
+                framing.observe(&buf[..bytes]);
+            }
         }
 
         Ok(bytes)
@@ -150,10 +497,16 @@ impl<T: AsyncRead + AsyncWrite> io::Write for Transport<T> {
     }
 
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let bytes = self.sense_err(move |io| io.write(buf))?;
+        let bytes = self.sense_err(|io| io.write(buf))?;
 
+        if bytes > 0 {
+            self.record_activity();
+        }
         if let Some(inner) = self.inner.as_mut() {
             inner.tx_bytes += bytes as u64;
+            if let Some(framing) = inner.tx_framing.as_mut() {
+                framing.observe(&buf[..bytes]);
+            }
         }
 
         Ok(bytes)
@@ -162,7 +515,7 @@ impl<T: AsyncRead + AsyncWrite> io::Write for Transport<T> {
 
 impl<T: AsyncRead + AsyncWrite> AsyncRead for Transport<T> {
     unsafe fn prepare_uninitialized_buffer(&self, buf: &mut [u8]) -> bool {
-        self.io.prepare_uninitialized_buffer(buf)
+        self.io_ref().prepare_uninitialized_buffer(buf)
     }
 }
 
@@ -172,10 +525,31 @@ impl<T: AsyncRead + AsyncWrite> AsyncWrite for Transport<T> {
     }
 
     fn write_buf<B: Buf>(&mut self, buf: &mut B) -> Poll<usize, io::Error> {
+        // `write_buf` advances `buf` itself, so the bytes it consumes aren't
+        // observable afterward; snapshot the head of the buffer up front
+        // (only when a tx framing decoder is actually installed) so it can
+        // still be sliced down to what was really written once `write_buf`
+        // reports how much that was.
+        let has_tx_framing = self.inner.as_ref().map_or(false, |i| i.tx_framing.is_some());
+        let head = if has_tx_framing {
+            Some(buf.bytes().to_vec())
+        } else {
+            None
+        };
+
         let bytes = try_ready!(self.sense_err(|io| io.write_buf(buf)));
 
+        if bytes > 0 {
+            self.record_activity();
+        }
         if let Some(inner) = self.inner.as_mut() {
             inner.tx_bytes += bytes as u64;
+            if let (Some(framing), Some(head)) = (inner.tx_framing.as_mut(), head.as_ref()) {
+                // `head` only captured the first contiguous chunk of `buf`;
+                // a vectored write can report more bytes written than that
+                // chunk held, so only observe what was actually captured.
+                framing.observe(&head[..bytes.min(head.len())]);
+            }
         }
 
         Ok(Async::Ready(bytes))
@@ -188,7 +562,7 @@ impl<T: AsyncRead + AsyncWrite + Peek> Peek for Transport<T> {
     }
 
     fn peeked(&self) -> &[u8] {
-        self.io.peeked()
+        self.io_ref().peeked()
     }
 }
 
@@ -216,9 +590,46 @@ where
         Connect {
             underlying,
             handle: handle.clone(),
+            otel: None,
+            pool: None,
+            idle_timeout: None,
+            framing: None,
             ctx: Arc::clone(ctx),
         }
     }
+
+    /// Enables OpenTelemetry span export for connections made through this `Connect`.
+    pub(super) fn with_otel_exporter(mut self, otel: OtelExporter) -> Self {
+        self.otel = Some(otel);
+        self
+    }
+
+    /// Enables a pool of idle connections, keyed by remote address and
+    /// metadata, bounded by `config`.
+    pub(super) fn with_pool(mut self, config: pool::PoolConfig) -> Self {
+        self.pool = Some(pool::Pool::new(config));
+        self
+    }
+
+    /// Shuts a connection down if it goes longer than `timeout` without any
+    /// successful read or write.
+    pub(super) fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Counts discrete protocol messages, read and written, on every
+    /// transport made through this `Connect`, by running `decoder` over its
+    /// bytes. The caller is expected to have already determined which
+    /// protocol this `Connect` speaks (typically via its own peek-based
+    /// protocol detection) before configuring `decoder`.
+    pub(super) fn with_framing<D>(mut self, decoder: D) -> Self
+    where
+        D: Decoder + Clone + Send + 'static,
+    {
+        self.framing = Some(FramingFactory::new(decoder));
+        self
+    }
 }
 
 impl<C> tokio_connect::Connect for Connect<C>
@@ -230,9 +641,34 @@ where
     type Future = Connecting<C>;
 
     fn connect(&self) -> Self::Future {
-        Connecting {
+        if let Some(ref pool) = self.pool {
+            let key = pool::Key::new(&self.ctx);
+            if let Some(idle) = pool.take(&key) {
+                let mut trans = Transport::reopen(idle, self.idle_timeout, pool.clone(), key);
+                if let Some(ref framing) = self.framing {
+                    trans.install_framing(framing);
+                }
+                return Connecting::Reused(Some(trans));
+            }
+
+            return Connecting::New {
+                underlying: self.underlying.connect(),
+                handle: self.handle.clone(),
+                otel: self.otel.clone(),
+                pool: Some((pool.clone(), key)),
+                idle_timeout: self.idle_timeout,
+                framing: self.framing.clone(),
+                ctx: Arc::clone(&self.ctx),
+            };
+        }
+
+        Connecting::New {
             underlying: self.underlying.connect(),
             handle: self.handle.clone(),
+            otel: self.otel.clone(),
+            pool: None,
+            idle_timeout: self.idle_timeout,
+            framing: self.framing.clone(),
             ctx: Arc::clone(&self.ctx),
         }
     }
@@ -248,16 +684,113 @@ where
     type Error = C::Error;
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        let io = try_ready!(self.underlying.poll());
-        debug!("client connection open");
-        let ctx = ctx::transport::Client::new(
-            &self.ctx.proxy,
-            &self.ctx.remote,
-            self.ctx.metadata.clone(),
-            io.tls_status,
-        );
-        let ctx = Arc::new(ctx.into());
-        let trans = Transport::open(io, Instant::now(), &self.handle, ctx);
-        Ok(trans.into())
+        match *self {
+            Connecting::Reused(ref mut trans) => {
+                let trans = trans.take().expect("Connecting::Reused polled after ready");
+                Ok(Async::Ready(trans))
+            }
+            Connecting::New {
+                ref mut underlying,
+                ref handle,
+                ref otel,
+                ref pool,
+                idle_timeout,
+                ref framing,
+                ref ctx,
+            } => {
+                let io = try_ready!(underlying.poll());
+                debug!("client connection open");
+                let status = io.tls_status;
+                let client_ctx = ctx::transport::Client::new(
+                    &ctx.proxy,
+                    &ctx.remote,
+                    ctx.metadata.clone(),
+                    status,
+                );
+                let client_ctx = Arc::new(client_ctx.into());
+
+                // `io.tcp_connected_at` marks the moment the TCP-level connect
+                // finished, just before any TLS negotiation began, so measuring
+                // from there isolates the handshake itself from however long the
+                // dial took. Plaintext connections never perform a handshake;
+                // only report a latency for connections that actually negotiated
+                // TLS.
+                let latency = match status {
+                    ctx::transport::TlsStatus::Success => Some(io.tcp_connected_at.elapsed()),
+                    _ => None,
+                };
+                let mut tls_handle = handle.clone();
+                let tls_ctx = Arc::clone(&client_ctx);
+                tls_handle.send(move || {
+                    event::Event::TlsHandshake(tls_ctx, event::TlsHandshake { latency, status })
+                });
+
+                let mut trans = Transport::open(
+                    io,
+                    Instant::now(),
+                    handle,
+                    otel.as_ref(),
+                    idle_timeout,
+                    client_ctx,
+                );
+                if let Some(ref framing) = framing {
+                    trans.install_framing(framing);
+                }
+                let trans = match *pool {
+                    Some((ref pool, ref key)) => trans.with_pool(pool.clone(), key.clone()),
+                    None => trans,
+                };
+                Ok(Async::Ready(trans))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    use futures::future;
+    use tokio::runtime::current_thread::Runtime;
+
+    #[test]
+    fn idle_timer_not_expired_before_timeout() {
+        let mut rt = Runtime::new().expect("runtime");
+        rt.block_on(future::lazy(|| {
+            let mut timer = IdleTimer::new(Duration::from_millis(50));
+            assert!(!timer.poll_expired());
+            Ok::<_, ()>(())
+        }))
+        .unwrap();
+    }
+
+    #[test]
+    fn idle_timer_expires_after_no_activity_for_timeout() {
+        let mut rt = Runtime::new().expect("runtime");
+        rt.block_on(future::lazy(|| {
+            let mut timer = IdleTimer::new(Duration::from_millis(10));
+            thread::sleep(Duration::from_millis(30));
+            assert!(timer.poll_expired());
+            Ok::<_, ()>(())
+        }))
+        .unwrap();
+    }
+
+    #[test]
+    fn idle_timer_reset_pushes_deadline_out() {
+        let mut rt = Runtime::new().expect("runtime");
+        rt.block_on(future::lazy(|| {
+            let mut timer = IdleTimer::new(Duration::from_millis(20));
+            thread::sleep(Duration::from_millis(10));
+            timer.reset(Instant::now());
+            thread::sleep(Duration::from_millis(10));
+            assert!(
+                !timer.poll_expired(),
+                "reset should have pushed the deadline past this sleep"
+            );
+            Ok::<_, ()>(())
+        }))
+        .unwrap();
     }
 }
@@ -0,0 +1,81 @@
+use std::fmt;
+
+use bytes::BytesMut;
+use tokio_io::codec::Decoder;
+
+use telemetry::event::FrameStats;
+
+fn record(stats: &mut FrameStats, size: usize) {
+    stats.frames += 1;
+    stats.total_size += size as u64;
+    stats.min_size = Some(stats.min_size.map_or(size, |m| m.min(size)));
+    stats.max_size = Some(stats.max_size.map_or(size, |m| m.max(size)));
+}
+
+/// Something that can observe bytes passing through a `Transport` in one
+/// direction and report how many discrete protocol messages they formed.
+pub(super) trait FrameSense: Send {
+    fn observe(&mut self, bytes: &[u8]);
+    fn stats(&self) -> FrameStats;
+}
+
+impl fmt::Debug for dyn FrameSense {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("FrameSense").field(&self.stats()).finish()
+    }
+}
+
+/// Counts discrete protocol messages by running a `Decoder` over the bytes
+/// observed, in pass-through fashion: the decoder only ever advances a
+/// private scratch buffer and never touches what the application actually
+/// reads or writes.
+///
+/// If the decoder errors — e.g. the stream doesn't actually speak the
+/// detected protocol, or desyncs for some other reason — frame counting is
+/// abandoned and the transport degrades to byte-only accounting.
+pub(super) struct FrameCounter<D> {
+    decoder: D,
+    buf: BytesMut,
+    stats: FrameStats,
+    broken: bool,
+}
+
+impl<D: Decoder> FrameCounter<D> {
+    pub(super) fn new(decoder: D) -> Self {
+        FrameCounter {
+            decoder,
+            buf: BytesMut::new(),
+            stats: FrameStats::default(),
+            broken: false,
+        }
+    }
+}
+
+impl<D: Decoder + Send> FrameSense for FrameCounter<D> {
+    fn observe(&mut self, bytes: &[u8]) {
+        if self.broken {
+            return;
+        }
+
+        self.buf.extend_from_slice(bytes);
+        loop {
+            let before = self.buf.len();
+            match self.decoder.decode(&mut self.buf) {
+                Ok(Some(_)) => {
+                    let consumed = before - self.buf.len();
+                    record(&mut self.stats, consumed);
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    self.broken = true;
+                    self.buf.clear();
+                    break;
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> FrameStats {
+        self.stats
+    }
+}
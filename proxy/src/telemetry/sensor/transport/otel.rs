@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use opentelemetry::api::{BoxedSpan, Key, Span, Tracer};
+use opentelemetry::global;
+
+use ctx;
+use telemetry::event;
+
+/// Mirrors transport lifecycle events as OpenTelemetry spans.
+///
+/// A `TransportOpen` starts a span keyed by the connection's `ctx`, tags it
+/// with the remote peer and TLS status if it is a client connection (server
+/// connections' spans get no such tags), and holds on to that same span; the
+/// matching `TransportClose`, whether raised from `sense_err` or from
+/// `Transport`'s `Drop`, looks it back up by that key, records the byte
+/// counters and `clean` flag onto it, and ends that exact span instance.
+/// `shutdown` ends any spans still outstanding at proxy shutdown.
+#[derive(Clone, Debug)]
+pub struct OtelExporter {
+    spans: Arc<Mutex<HashMap<CtxKey, BoxedSpan>>>,
+}
+
+/// Identifies a connection's span without holding on to its `Ctx`.
+type CtxKey = usize;
+
+impl OtelExporter {
+    pub fn new() -> Self {
+        OtelExporter {
+            spans: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn key(ctx: &Arc<ctx::transport::Ctx>) -> CtxKey {
+        Arc::as_ptr(ctx) as CtxKey
+    }
+
+    /// Starts a span for a newly-opened transport.
+    pub fn open(&self, ctx: &Arc<ctx::transport::Ctx>) {
+        let tracer = global::trace_provider().get_tracer("linkerd2-proxy");
+        let mut span = tracer.start(span_name(ctx), None);
+        tracer.mark_span_as_active(&span);
+
+        if let ctx::transport::Ctx::Client(ref client) = **ctx {
+            span.set_attribute(Key::new("remote").string(client.remote.to_string()));
+            span.set_attribute(Key::new("tls_status").string(format!("{:?}", client.tls_status)));
+        }
+
+        self.spans
+            .lock()
+            .expect("otel span table poisoned")
+            .insert(Self::key(ctx), span);
+    }
+
+    /// Ends the span started by the matching `open`, if telemetry for this
+    /// connection wasn't already closed.
+    pub fn close(&self, ctx: &Arc<ctx::transport::Ctx>, close: &event::TransportClose) {
+        let span = self
+            .spans
+            .lock()
+            .expect("otel span table poisoned")
+            .remove(&Self::key(ctx));
+
+        if let Some(mut span) = span {
+            span.set_attribute(Key::new("clean").bool(close.clean));
+            span.set_attribute(Key::new("close_reason").string(format!("{:?}", close.reason)));
+            span.set_attribute(Key::new("rx_bytes").i64(close.rx_bytes as i64));
+            span.set_attribute(Key::new("tx_bytes").i64(close.tx_bytes as i64));
+            span.set_attribute(Key::new("duration_ms").i64(duration_ms(close)));
+            span.end();
+        }
+    }
+
+    /// Ends every span this exporter is still tracking and flushes the
+    /// configured trace provider, so a proxy shutdown doesn't drop telemetry
+    /// for connections that were never closed first. A pooled connection's
+    /// span is already ended when it is parked, so parked connections have
+    /// no span outstanding here.
+    ///
+    /// The caller is expected to invoke this once, as part of the proxy's own
+    /// shutdown sequence, after it has stopped accepting new connections.
+    pub fn shutdown(&self) {
+        let mut spans = self.spans.lock().expect("otel span table poisoned");
+        for (_, mut span) in spans.drain() {
+            span.end();
+        }
+        drop(spans);
+
+        global::trace_provider().shutdown();
+    }
+}
+
+fn span_name(ctx: &Arc<ctx::transport::Ctx>) -> &'static str {
+    match **ctx {
+        ctx::transport::Ctx::Client(_) => "outbound_connection",
+        ctx::transport::Ctx::Server(_) => "inbound_connection",
+    }
+}
+
+fn duration_ms(close: &event::TransportClose) -> i64 {
+    let millis = close.duration.as_secs() * 1_000 + u64::from(close.duration.subsec_millis());
+    millis as i64
+}
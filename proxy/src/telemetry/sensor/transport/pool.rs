@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::{Async, Poll};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use connection::Peek;
+use ctx;
+use telemetry::event::FrameStats;
+
+use super::otel::OtelExporter;
+
+/// Identifies a pool of idle connections bound for the same destination.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Key {
+    remote: SocketAddr,
+    metadata: ctx::transport::Metadata,
+}
+
+impl Key {
+    pub fn new(ctx: &ctx::transport::Client) -> Self {
+        Key {
+            remote: ctx.remote,
+            metadata: ctx.metadata.clone(),
+        }
+    }
+}
+
+/// Bounds on how many idle connections a `Pool` retains, and for how long.
+#[derive(Clone, Copy, Debug)]
+pub struct PoolConfig {
+    pub max_idle_per_key: usize,
+    pub max_idle_age: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        PoolConfig {
+            max_idle_per_key: 8,
+            max_idle_age: Duration::from_secs(60),
+        }
+    }
+}
+
+/// A parked, telemetry-wrapped transport, along with the bookkeeping needed
+/// to restore it to a live `Transport` if it's checked out again.
+#[derive(Debug)]
+pub(super) struct Idle<T> {
+    pub(super) io: T,
+    pub(super) ctx: Arc<ctx::transport::Ctx>,
+    pub(super) handle: super::super::Handle,
+    pub(super) otel: Option<OtelExporter>,
+    pub(super) opened_at: Instant,
+    pub(super) rx_bytes: u64,
+    pub(super) tx_bytes: u64,
+    /// Frame stats accumulated over every checkout so far, carried forward
+    /// so a connection's eventual close reports the same pooled lifetime
+    /// for frames as it already does for bytes and duration.
+    pub(super) rx_frames: Option<FrameStats>,
+    pub(super) tx_frames: Option<FrameStats>,
+    pub(super) parked_at: Instant,
+}
+
+/// A keyed store of idle connections available for reuse, modeled on the
+/// idle-connection pools used by HTTP client connectors.
+#[derive(Clone, Debug)]
+pub struct Pool<T> {
+    config: PoolConfig,
+    idle: Arc<Mutex<HashMap<Key, Vec<Idle<T>>>>>,
+}
+
+impl<T> Pool<T> {
+    pub fn new(config: PoolConfig) -> Self {
+        Pool {
+            config,
+            idle: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Removes and returns a still-healthy idle connection for `key`, if one
+    /// is parked. Stale or half-closed candidates are discarded along the
+    /// way rather than handed out.
+    pub(super) fn take(&self, key: &Key) -> Option<Idle<T>>
+    where
+        T: AsyncRead + AsyncWrite + Peek,
+    {
+        let mut idle = self.idle.lock().expect("pool lock poisoned");
+        let entries = idle.get_mut(key)?;
+
+        while let Some(mut candidate) = entries.pop() {
+            if is_stale(candidate.parked_at, self.config.max_idle_age) {
+                continue;
+            }
+
+            if is_reusable(&candidate.io.poll_peek()) {
+                return Some(candidate);
+            }
+        }
+
+        None
+    }
+
+    /// Parks an idle connection under `key`, dropping it instead if the
+    /// pool is already at capacity for that key.
+    pub(super) fn put(&self, key: Key, idle: Idle<T>) {
+        let mut pool = self.idle.lock().expect("pool lock poisoned");
+        let entries = pool.entry(key).or_insert_with(Vec::new);
+        if entries.len() < self.config.max_idle_per_key {
+            entries.push(idle);
+        }
+    }
+}
+
+/// Returns `true` once a parked connection has sat idle longer than `max_idle_age`.
+fn is_stale(parked_at: Instant, max_idle_age: Duration) -> bool {
+    parked_at.elapsed() > max_idle_age
+}
+
+/// Returns `true` if a parked connection's `poll_peek` result means it's
+/// still safe to hand back out. A readable idle socket means either the peer
+/// hung up (0 bytes, EOF) or sent something unsolicited; either way it's not
+/// safe to reuse.
+fn is_reusable(peek: &Poll<usize, io::Error>) -> bool {
+    match peek {
+        Ok(Async::NotReady) => true,
+        Ok(Async::Ready(_)) | Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_stale_within_max_age_is_not_stale() {
+        let parked_at = Instant::now();
+        assert!(!is_stale(parked_at, Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn is_stale_past_max_age_is_stale() {
+        let parked_at = Instant::now() - Duration::from_millis(50);
+        assert!(is_stale(parked_at, Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn is_reusable_when_not_ready() {
+        assert!(is_reusable(&Ok(Async::NotReady)));
+    }
+
+    #[test]
+    fn is_reusable_false_when_readable() {
+        assert!(!is_reusable(&Ok(Async::Ready(0))));
+    }
+
+    #[test]
+    fn is_reusable_false_on_peek_error() {
+        let err = Err(io::Error::new(io::ErrorKind::Other, "peek failed"));
+        assert!(!is_reusable(&err));
+    }
+}